@@ -0,0 +1,176 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::{
+    MockSocketDriver, MockThermometerDriver, Room, SmartDevice, SmartHouse, SmartSocket,
+    SmartThermometer, SocketDriver, TcpSocketDriver, ThermometerDriver, UdpThermometerDriver,
+    DeviceError,
+};
+
+/// Ошибка загрузки `SmartHouse` из TOML-конфигурации.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    UnknownKind { kind: String },
+    UnknownDriver { kind: String, driver_type: String },
+    DuplicateDevice { room: String, device: String },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "не удалось прочитать файл конфигурации: {}", e),
+            ConfigError::Parse(e) => write!(f, "не удалось разобрать конфигурацию: {}", e),
+            ConfigError::UnknownKind { kind } => {
+                write!(f, "неизвестный тип устройства '{}'", kind)
+            }
+            ConfigError::UnknownDriver { kind, driver_type } => write!(
+                f,
+                "драйвер '{}' не поддерживается для устройства типа '{}'",
+                driver_type, kind
+            ),
+            ConfigError::DuplicateDevice { room, device } => write!(
+                f,
+                "устройство '{}' уже определено в комнате '{}'",
+                device, room
+            ),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io(e) => Some(e),
+            ConfigError::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        ConfigError::Parse(e)
+    }
+}
+
+#[derive(Deserialize)]
+struct HouseConfig {
+    room: Vec<RoomConfig>,
+}
+
+#[derive(Deserialize)]
+struct RoomConfig {
+    name: String,
+    device: Vec<DeviceConfig>,
+}
+
+#[derive(Deserialize)]
+struct DeviceConfig {
+    kind: String,
+    name: String,
+    driver: DriverConfig,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum DriverConfig {
+    Tcp {
+        addr: String,
+    },
+    Udp {
+        bind: String,
+    },
+    Mock {
+        #[serde(default)]
+        state: bool,
+        #[serde(default)]
+        power: f32,
+        #[serde(default)]
+        temperature: f32,
+    },
+}
+
+impl SmartHouse {
+    /// Строит дом целиком из TOML-файла с описанием комнат и устройств.
+    ///
+    /// Формат см. в теле модуля `config`: `[[room]]` с `name`, затем
+    /// `[[room.device]]` с `kind`, `name` и таблицей `driver`.
+    pub fn from_config<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let text = fs::read_to_string(path)?;
+        let house_cfg: HouseConfig = toml::from_str(&text)?;
+
+        let mut rooms = HashMap::new();
+        for room_cfg in house_cfg.room {
+            let mut devices = HashMap::new();
+            for device_cfg in room_cfg.device {
+                if devices.contains_key(&device_cfg.name) {
+                    return Err(ConfigError::DuplicateDevice {
+                        room: room_cfg.name.clone(),
+                        device: device_cfg.name,
+                    });
+                }
+                let device = build_device(&room_cfg.name, device_cfg)?;
+                devices.insert(device.name(), device);
+            }
+            let room = Room::new(&room_cfg.name, devices);
+            rooms.insert(room.name().clone(), room);
+        }
+
+        Ok(SmartHouse::new(rooms))
+    }
+}
+
+fn build_device(room_name: &str, cfg: DeviceConfig) -> Result<SmartDevice, ConfigError> {
+    match cfg.kind.as_str() {
+        "socket" => {
+            let driver: Box<dyn SocketDriver<Error = DeviceError>> = match cfg.driver {
+                DriverConfig::Tcp { addr } => Box::new(TcpSocketDriver::new(&addr)),
+                DriverConfig::Mock { state, power, .. } => {
+                    Box::new(MockSocketDriver::new(state, power))
+                }
+                DriverConfig::Udp { .. } => {
+                    return Err(ConfigError::UnknownDriver {
+                        kind: cfg.kind,
+                        driver_type: "udp".to_string(),
+                    })
+                }
+            };
+            Ok(SmartDevice::Socket(SmartSocket::new(&cfg.name, driver)))
+        }
+        "thermometer" => {
+            let driver: Box<dyn ThermometerDriver<Error = DeviceError>> = match cfg.driver {
+                DriverConfig::Udp { bind } => {
+                    Box::new(UdpThermometerDriver::new(&cfg.name, &bind, false))
+                }
+                DriverConfig::Mock { temperature, .. } => {
+                    Box::new(MockThermometerDriver::new(temperature))
+                }
+                DriverConfig::Tcp { .. } => {
+                    return Err(ConfigError::UnknownDriver {
+                        kind: cfg.kind,
+                        driver_type: "tcp".to_string(),
+                    })
+                }
+            };
+            Ok(SmartDevice::Thermometer(SmartThermometer::new(
+                &cfg.name, room_name, driver,
+            )))
+        }
+        other => Err(ConfigError::UnknownKind {
+            kind: other.to_string(),
+        }),
+    }
+}
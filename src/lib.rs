@@ -1,10 +1,22 @@
+mod config;
+mod sensor_protocol;
+mod server;
+
+pub use config::ConfigError;
+pub use sensor_protocol::{SensorReading, WireFormat};
+pub use server::{
+    run_house_server, spawn_house_server, HouseClientDriver, HouseRequest, HouseResponse,
+};
+
+use sensor_protocol::BufferPool;
+
 use getset::{Getters, Setters};
-use rand::Rng;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{self, Debug, Display, Formatter};
-use std::io::{Read, Write};
-use std::net::{TcpStream, TcpListener};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, TcpListener};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::net::UdpSocket;
 use std::thread;
@@ -13,15 +25,81 @@ pub trait Report {
     fn print_report(&self);
 }
 
+/// Событие, которым устройство извещает подписчиков об изменении своего
+/// состояния — см. `SmartDevice::register_update` / `SmartHouse::register_update`.
+#[derive(Clone, Debug)]
+pub enum DeviceUpdate {
+    SocketState { device: String, is_on: bool, power: f32 },
+    Temperature { device: String, temperature: f32 },
+}
+
+/// Подписка на `DeviceUpdate`: обычная замыкающая функция, вызываемая из
+/// потока, в котором произошло изменение (опрос или фоновый поток драйвера).
+pub type UpdateCallback = Box<dyn Fn(&DeviceUpdate) + Send + Sync>;
+
+/// Единая ошибка для всех драйверов устройств.
+///
+/// `SocketDriver`/`ThermometerDriver` реализации используют её как свой
+/// ассоциированный тип ошибки, а `SmartHouseError` оборачивает её, чтобы
+/// сбой устройства можно было отличить от сбоя адресации дома.
+#[derive(Debug)]
+pub enum DeviceError {
+    /// Не удалось установить или использовать соединение с устройством.
+    Connection(io::Error),
+    /// Устройство ответило данными, которые не удалось разобрать.
+    Protocol(String),
+    /// Драйвер ещё не получил ни одного показания.
+    NoData,
+    /// Операция не уложилась в отведённое время.
+    Timeout,
+}
+
+impl Display for DeviceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DeviceError::Connection(e) => write!(f, "ошибка соединения с устройством: {}", e),
+            DeviceError::Protocol(msg) => write!(f, "ошибка протокола устройства: {}", msg),
+            DeviceError::NoData => write!(f, "нет данных от устройства"),
+            DeviceError::Timeout => write!(f, "истекло время ожидания ответа устройства"),
+        }
+    }
+}
+
+impl Error for DeviceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DeviceError::Connection(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DeviceError {
+    fn from(e: io::Error) -> Self {
+        DeviceError::Connection(e)
+    }
+}
+
 pub trait SocketDriver: Send + Sync + Debug {
-    fn turn_on(&mut self) -> Result<(), Box<dyn Error>>;
-    fn turn_off(&mut self) -> Result<(), Box<dyn Error>>;
-    fn is_on(&self) -> Result<bool, Box<dyn Error>>;
-    fn current_power(&self) -> Result<f32, Box<dyn Error>>;
+    type Error: Error + Send + Sync + 'static;
+
+    fn turn_on(&mut self) -> Result<(), Self::Error>;
+    fn turn_off(&mut self) -> Result<(), Self::Error>;
+    fn is_on(&self) -> Result<bool, Self::Error>;
+    fn current_power(&self) -> Result<f32, Self::Error>;
 }
 
 pub trait ThermometerDriver: Send + Sync + Debug {
-    fn latest_temperature(&self) -> Result<f32, Box<dyn Error>>;
+    type Error: Error + Send + Sync + 'static;
+
+    fn latest_temperature(&self) -> Result<f32, Self::Error>;
+
+    /// Подписывает `callback` на обновления, замеченные драйвером напрямую
+    /// (например фоновым потоком, читающим сокет). Реализация по умолчанию
+    /// просто отбрасывает `callback`: термометр с таким драйвером не
+    /// присылает обновлений, и опрос через `SmartThermometer` их тоже не
+    /// порождает.
+    fn register_update(&self, _callback: UpdateCallback) {}
 }
 
 #[derive(Clone, Debug)]
@@ -34,7 +112,7 @@ impl TcpSocketDriver {
         Self { addr: addr.to_string() }
     }
 
-    fn send_cmd(&self, cmd: &str) -> Result<String, Box<dyn Error>> {
+    fn send_cmd(&self, cmd: &str) -> Result<String, DeviceError> {
         let mut stream = TcpStream::connect(&self.addr)?;
         stream.write_all(cmd.as_bytes())?;
         stream.flush()?;
@@ -46,24 +124,28 @@ impl TcpSocketDriver {
 
 
 impl SocketDriver for TcpSocketDriver {
-    fn turn_on(&mut self) -> Result<(), Box<dyn Error>> {
+    type Error = DeviceError;
+
+    fn turn_on(&mut self) -> Result<(), DeviceError> {
         self.send_cmd("ON")?;
         Ok(())
     }
-    fn turn_off(&mut self) -> Result<(), Box<dyn Error>> {
+    fn turn_off(&mut self) -> Result<(), DeviceError> {
         self.send_cmd("OFF")?;
         Ok(())
     }
 
-    fn is_on(&self) -> Result<bool, Box<dyn Error>> {
+    fn is_on(&self) -> Result<bool, DeviceError> {
         let resp = self.send_cmd("STATE")?;
         Ok(resp.trim() == "ON")
     }
 
 
-    fn current_power(&self) -> Result<f32, Box<dyn Error>> {
+    fn current_power(&self) -> Result<f32, DeviceError> {
         let resp = self.send_cmd("POWER")?;
-        Ok(resp.trim().parse()?)
+        resp.trim()
+            .parse()
+            .map_err(|_| DeviceError::Protocol(format!("некорректное значение мощности: '{}'", resp.trim())))
     }
 }
 
@@ -79,62 +161,134 @@ impl MockSocketDriver {
 }
 
 impl SocketDriver for MockSocketDriver {
-    fn turn_on(&mut self) -> Result<(), Box<dyn Error>> {
+    type Error = DeviceError;
+
+    fn turn_on(&mut self) -> Result<(), DeviceError> {
         self.state.lock().unwrap().0 = true;
         Ok(())
     }
-    fn turn_off(&mut self) -> Result<(), Box<dyn Error>> {
+    fn turn_off(&mut self) -> Result<(), DeviceError> {
         self.state.lock().unwrap().0 = false;
         Ok(())
     }
 
-    fn is_on(&self) -> Result<bool, Box<dyn Error>> {
+    fn is_on(&self) -> Result<bool, DeviceError> {
         Ok(self.state.lock().unwrap().0)
     }
-    fn current_power(&self) -> Result<f32, Box<dyn Error>> {
+    fn current_power(&self) -> Result<f32, DeviceError> {
         let (on, power) = *self.state.lock().unwrap();
         Ok(if on { power } else { 0.0 })
     }
 }
 
-#[derive(Clone, Debug)]
+/// Размер буфера в пуле приёма: с запасом над `FRAME_LEN_WITH_SENSOR`, чтобы
+/// туда же помещались ASCII-показания в режиме обратной совместимости.
+const RECV_BUF_SIZE: usize = 32;
+/// Сколько буферов держит пул приёма — достаточно, чтобы несколько
+/// датчиков могли перемежать свои пакеты без лишних аллокаций.
+const RECV_POOL_SIZE: usize = 4;
+
+#[derive(Clone)]
 pub struct UdpThermometerDriver {
-    latest_temp: Arc<Mutex<Option<f32>>>,
+    latest_reading: Arc<Mutex<Option<SensorReading>>>,
+    callbacks: Arc<Mutex<Vec<UpdateCallback>>>,
+    local_addr: SocketAddr,
 }
 
-impl UdpThermometerDriver {
-    pub fn new(bind_addr: &str) -> Self {
-        let latest_temp = Arc::new(Mutex::new(None));
-        let latest_temp_clone = Arc::clone(&latest_temp);
+impl Debug for UdpThermometerDriver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UdpThermometerDriver")
+            .field("latest_reading", &self.latest_reading)
+            .field("local_addr", &self.local_addr)
+            .finish_non_exhaustive()
+    }
+}
 
-        let addr = bind_addr.to_string();
+impl UdpThermometerDriver {
+    /// Создаёт драйвер, читающий бинарный протокол на `bind_addr`. Если
+    /// `accept_legacy_ascii` выставлен, пакеты, не разобравшиеся как
+    /// бинарный кадр, дополнительно пробуются как старый ASCII-формат —
+    /// это позволяет мультиплексировать старые и новые датчики на одном порту.
+    pub fn new(name: &str, bind_addr: &str, accept_legacy_ascii: bool) -> Self {
+        let socket = UdpSocket::bind(bind_addr).expect("UDP bind failed");
+        socket
+            .set_read_timeout(Some(std::time::Duration::from_millis(200)))
+            .unwrap();
+        let local_addr = socket.local_addr().expect("UDP socket has no local address");
+
+        let latest_reading: Arc<Mutex<Option<SensorReading>>> = Arc::new(Mutex::new(None));
+        let latest_reading_clone = Arc::clone(&latest_reading);
+        let callbacks: Arc<Mutex<Vec<UpdateCallback>>> = Arc::new(Mutex::new(Vec::new()));
+        let callbacks_clone = Arc::clone(&callbacks);
+
+        let device_name = name.to_string();
         thread::spawn(move || {
-            let socket = UdpSocket::bind(&addr).expect("UDP bind failed");
-            socket.set_nonblocking(true).unwrap();
-            let mut buf = [0u8; 64];
+            let mut pool: BufferPool<RECV_BUF_SIZE> = BufferPool::new(RECV_POOL_SIZE);
 
             loop {
-                if let Ok((len, _)) = socket.recv_from(&mut buf) {
-                    if let Ok(s) = std::str::from_utf8(&buf[..len]) {
-                        if let Ok(temp) = s.trim().parse::<f32>() {
-                            *latest_temp_clone.lock().unwrap() = Some(temp);
+                let buf = pool.acquire();
+                let reading = match socket.recv_from(buf) {
+                    Ok((len, _)) => sensor_protocol::decode(&buf[..len]).or_else(|| {
+                        if accept_legacy_ascii {
+                            sensor_protocol::decode_legacy_ascii(&buf[..len])
+                        } else {
+                            None
+                        }
+                    }),
+                    Err(_) => None,
+                };
+
+                if let Some(reading) = reading {
+                    let mut latest = latest_reading_clone.lock().unwrap();
+                    let changed = !matches!(
+                        *latest,
+                        Some(prev) if prev.temperature == reading.temperature && prev.sensor_id == reading.sensor_id
+                    );
+                    *latest = Some(reading);
+                    drop(latest);
+
+                    if changed {
+                        let update = DeviceUpdate::Temperature {
+                            device: device_name.clone(),
+                            temperature: reading.temperature,
+                        };
+                        for cb in callbacks_clone.lock().unwrap().iter() {
+                            cb(&update);
                         }
                     }
                 }
-                thread::sleep(std::time::Duration::from_millis(200));
             }
         });
 
-        Self { latest_temp }
+        Self { latest_reading, callbacks, local_addr }
+    }
+
+    /// Адрес, на который реально привязался приёмный сокет — полезно, когда
+    /// `bind_addr` указывал порт `0` и нужно узнать, какой порт выбрала ОС.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Метка времени (мс) последнего полученного показания — позволяет
+    /// вызывающей стороне самостоятельно решить, не протухло ли оно.
+    pub fn last_reading_timestamp_ms(&self) -> Option<u64> {
+        self.latest_reading.lock().unwrap().map(|r| r.timestamp_ms)
     }
 }
 
 impl ThermometerDriver for UdpThermometerDriver {
-    fn latest_temperature(&self) -> Result<f32, Box<dyn Error>> {
-        self.latest_temp
+    type Error = DeviceError;
+
+    fn latest_temperature(&self) -> Result<f32, DeviceError> {
+        self.latest_reading
             .lock()
             .unwrap()
-            .ok_or_else(|| "Нет данных от термометра".into())
+            .map(|r| r.temperature)
+            .ok_or(DeviceError::NoData)
+    }
+
+    fn register_update(&self, callback: UpdateCallback) {
+        self.callbacks.lock().unwrap().push(callback);
     }
 }
 
@@ -150,7 +304,9 @@ impl MockThermometerDriver {
 }
 
 impl ThermometerDriver for MockThermometerDriver {
-    fn latest_temperature(&self) -> Result<f32, Box<dyn Error>> {
+    type Error = DeviceError;
+
+    fn latest_temperature(&self) -> Result<f32, DeviceError> {
         Ok(self.temp)
     }
 }
@@ -159,85 +315,141 @@ impl ThermometerDriver for MockThermometerDriver {
 pub struct SmartThermometer {
     name: String,
     location: String,
-    driver: Box<dyn ThermometerDriver>,
+    driver: Box<dyn ThermometerDriver<Error = DeviceError>>,
 }
 
 pub trait Thermometer {
-    fn get_current_temperature(&self) -> f32;
+    fn get_current_temperature(&self) -> Result<f32, DeviceError>;
 }
 
 impl SmartThermometer {
-    pub fn new(name: &str, location: &str, driver: Box<dyn ThermometerDriver>) -> Self {
+    pub fn new(name: &str, location: &str, driver: Box<dyn ThermometerDriver<Error = DeviceError>>) -> Self {
         Self {
             name: name.to_string(),
             location: location.to_string(),
             driver
         }
     }
+
+    /// Подписывает `callback` на обновления температуры этого термометра.
+    /// Подписка передаётся драйверу; если драйвер не переопределяет
+    /// `ThermometerDriver::register_update`, обновления не приходят.
+    pub fn register_update(&self, callback: impl Fn(&DeviceUpdate) + Send + Sync + 'static) {
+        self.driver.register_update(Box::new(callback));
+    }
 }
 
 impl Thermometer for SmartThermometer {
-    fn get_current_temperature(&self) -> f32 {
-        self.driver.latest_temperature().unwrap_or_else(|_| 0.0)
+    fn get_current_temperature(&self) -> Result<f32, DeviceError> {
+        self.driver.latest_temperature()
     }
 }
 
 impl Display for SmartThermometer {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "Термометр '{}' в '{}' показывает {:.1}°C",
-            self.name,
-            self.location,
-            self.get_current_temperature()
-        )
+        match self.get_current_temperature() {
+            Ok(temp) => write!(
+                f,
+                "Термометр '{}' в '{}' показывает {:.1}°C",
+                self.name, self.location, temp
+            ),
+            Err(e) => write!(
+                f,
+                "Термометр '{}' в '{}': {}",
+                self.name, self.location, e
+            ),
+        }
     }
 }
 
-#[derive(Debug)]
 pub struct SmartSocket {
     pub name: String,
-    driver: Box<dyn SocketDriver>
+    driver: Box<dyn SocketDriver<Error = DeviceError>>,
+    callbacks: Arc<Mutex<Vec<UpdateCallback>>>,
+    last_observed: Mutex<Option<(bool, f32)>>,
+}
+
+impl Debug for SmartSocket {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmartSocket")
+            .field("name", &self.name)
+            .field("driver", &self.driver)
+            .finish_non_exhaustive()
+    }
 }
 
 impl SmartSocket {
-    pub fn new(name: &str, driver: Box<dyn SocketDriver>) -> Self {
+    pub fn new(name: &str, driver: Box<dyn SocketDriver<Error = DeviceError>>) -> Self {
         Self {
             name: name.to_string(),
             driver,
+            callbacks: Arc::new(Mutex::new(Vec::new())),
+            last_observed: Mutex::new(None),
+        }
+    }
+
+    /// Подписывает `callback` на обновления состояния этой розетки.
+    pub fn register_update(&self, callback: impl Fn(&DeviceUpdate) + Send + Sync + 'static) {
+        self.callbacks.lock().unwrap().push(Box::new(callback));
+    }
+
+    fn notify_if_changed(&self, is_on: bool, power: f32) {
+        let mut last = self.last_observed.lock().unwrap();
+        if *last != Some((is_on, power)) {
+            *last = Some((is_on, power));
+            let update = DeviceUpdate::SocketState {
+                device: self.name.clone(),
+                is_on,
+                power,
+            };
+            for cb in self.callbacks.lock().unwrap().iter() {
+                cb(&update);
+            }
         }
     }
 
-    pub fn turn_on(&mut self) {
-        self.driver.turn_on().expect("Ошибка включения розетки");
+    pub fn turn_on(&mut self) -> Result<(), DeviceError> {
+        self.driver.turn_on()?;
+        if let Ok(power) = self.driver.current_power() {
+            self.notify_if_changed(true, power);
+        }
+        Ok(())
     }
 
-    pub fn turn_off(&mut self) {
-        self.driver.turn_off().expect("Ошибка выключения розетки");
+    pub fn turn_off(&mut self) -> Result<(), DeviceError> {
+        self.driver.turn_off()?;
+        if let Ok(power) = self.driver.current_power() {
+            self.notify_if_changed(false, power);
+        }
+        Ok(())
     }
 
-    pub fn is_on(&self) -> bool {
-        self.driver.is_on().unwrap_or_else(|_| false)
+    pub fn is_on(&self) -> Result<bool, DeviceError> {
+        self.driver.is_on()
     }
 
-    pub fn current_power(&self) -> f32 {
-        self.driver.current_power().unwrap_or_else(|_| 0.0)
+    pub fn current_power(&self) -> Result<f32, DeviceError> {
+        let power = self.driver.current_power()?;
+        let last = *self.last_observed.lock().unwrap();
+        if let Some((on, _)) = last {
+            self.notify_if_changed(on, power);
+        }
+        Ok(power)
     }
 }
 
 impl Display for SmartSocket {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "Розетка '{}' сейчас {}. Мощность: {:.1} Вт",
-            self.name,
-            if self.is_on() {
-                "включена"
-            } else {
-                "выключена"
-            },
-            self.current_power()
-        )
+        match (self.is_on(), self.current_power()) {
+            (Ok(on), Ok(power)) => write!(
+                f,
+                "Розетка '{}' сейчас {}. Мощность: {:.1} Вт",
+                self.name,
+                if on { "включена" } else { "выключена" },
+                power
+            ),
+            (Err(e), _) | (_, Err(e)) => write!(f, "Розетка '{}': {}", self.name, e),
+        }
     }
 }
 
@@ -254,6 +466,15 @@ impl SmartDevice {
             SmartDevice::Socket(s) => s.name.clone(),
         }
     }
+
+    /// Подписывает `callback` на обновления этого устройства, будь то
+    /// розетка или термометр.
+    pub fn register_update(&self, callback: impl Fn(&DeviceUpdate) + Send + Sync + 'static) {
+        match self {
+            SmartDevice::Thermometer(t) => t.register_update(callback),
+            SmartDevice::Socket(s) => s.register_update(callback),
+        }
+    }
 }
 
 impl Report for SmartDevice {
@@ -295,6 +516,10 @@ impl Room {
     pub fn get_device_mut(&mut self, key: &str) -> Option<&mut SmartDevice> {
         self.devices.get_mut(key)
     }
+
+    pub fn device_names(&self) -> Vec<String> {
+        self.devices.keys().cloned().collect()
+    }
 }
 
 impl Report for Room {
@@ -330,6 +555,22 @@ impl SmartHouse {
         self.rooms.remove(key)
     }
 
+    pub fn room_names(&self) -> Vec<String> {
+        self.rooms.keys().cloned().collect()
+    }
+
+    /// Подписывает `callback` на обновления каждого устройства дома.
+    pub fn register_update(
+        &self,
+        callback: impl Fn(&DeviceUpdate) + Send + Sync + Clone + 'static,
+    ) {
+        for room in self.rooms.values() {
+            for device in room.devices.values() {
+                device.register_update(callback.clone());
+            }
+        }
+    }
+
     pub fn get_device(
         &self,
         room_name: &str,
@@ -376,6 +617,7 @@ impl Report for SmartHouse {
 pub enum SmartHouseError {
     RoomNotFound(String),
     DeviceNotFound { room: String, device: String },
+    Device(DeviceError),
 }
 
 impl Display for SmartHouseError {
@@ -385,11 +627,25 @@ impl Display for SmartHouseError {
             SmartHouseError::DeviceNotFound { room, device } => {
                 write!(f, "Устройство '{}' не найдено в комнате '{}'", device, room)
             }
+            SmartHouseError::Device(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for SmartHouseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SmartHouseError::Device(e) => Some(e),
+            _ => None,
         }
     }
 }
 
-impl Error for SmartHouseError {}
+impl From<DeviceError> for SmartHouseError {
+    fn from(e: DeviceError) -> Self {
+        SmartHouseError::Device(e)
+    }
+}
 
 #[macro_export]
 macro_rules! room {
@@ -439,12 +695,119 @@ fn handle_client(mut stream: TcpStream, state: Arc<Mutex<(bool, f32)>>) {
 }
 
 //UDP-термометр
-pub fn run_thermometer_emulator(target_addr: &str, period_ms: u64) {
+pub fn run_thermometer_emulator(target_addr: &str, period_ms: u64, format: WireFormat) {
     let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
+    let mut buf = [0u8; sensor_protocol::FRAME_LEN_WITH_SENSOR];
     loop {
         let temp = rand::random::<f32>() * 30.0;
-        let msg = format!("{:.2}", temp);
-        let _ = socket.send_to(msg.as_bytes(), target_addr);
+        match format {
+            WireFormat::Binary => {
+                let reading = SensorReading {
+                    timestamp_ms: sensor_protocol::now_ms(),
+                    temperature: temp,
+                    sensor_id: None,
+                };
+                let len = sensor_protocol::encode(&mut buf, &reading);
+                let _ = socket.send_to(&buf[..len], target_addr);
+            }
+            WireFormat::LegacyAscii => {
+                let msg = format!("{:.2}", temp);
+                let _ = socket.send_to(msg.as_bytes(), target_addr);
+            }
+        }
         thread::sleep(std::time::Duration::from_millis(period_ms));
     }
 }
+
+/// Хендл на имитатор, запущенный через `spawn_socket_emulator`/
+/// `spawn_thermometer_emulator`: останавливает фоновый поток и дожидается
+/// его завершения при `Drop`, в отличие от `run_socket_emulator`/
+/// `run_thermometer_emulator`, которые крутятся вечно.
+pub struct EmulatorHandle {
+    stop: Arc<AtomicBool>,
+    join: Option<thread::JoinHandle<()>>,
+}
+
+impl EmulatorHandle {
+    pub(crate) fn new(stop: Arc<AtomicBool>, join: thread::JoinHandle<()>) -> Self {
+        Self { stop, join: Some(join) }
+    }
+
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for EmulatorHandle {
+    fn drop(&mut self) {
+        self.stop();
+        if let Some(join) = self.join.take() {
+            let _ = join.join();
+        }
+    }
+}
+
+/// Как `run_socket_emulator`, но привязывается к переданному адресу (можно
+/// указать `:0`, чтобы получить свободный порт), возвращает реальный
+/// привязанный адрес и останавливается при `Drop` хендла. Рассчитан на
+/// интеграционные тесты, которым нужно и эфемерный порт, и чистую остановку.
+pub fn spawn_socket_emulator(addr: &str, initial_power: f32) -> (EmulatorHandle, String) {
+    let listener = TcpListener::bind(addr).unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let bound_addr = listener.local_addr().unwrap().to_string();
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_clone = Arc::clone(&stop);
+    let state = Arc::new(Mutex::new((false, initial_power)));
+
+    let join = thread::spawn(move || {
+        for stream in listener.incoming() {
+            if stop_clone.load(Ordering::SeqCst) {
+                break;
+            }
+            match stream {
+                Ok(stream) => {
+                    let st = Arc::clone(&state);
+                    thread::spawn(move || handle_client(stream, st));
+                }
+                Err(_) => thread::sleep(std::time::Duration::from_millis(20)),
+            }
+        }
+    });
+
+    (EmulatorHandle { stop, join: Some(join) }, bound_addr)
+}
+
+/// Как `run_thermometer_emulator`, но останавливается при `Drop` хендла
+/// вместо того, чтобы крутиться вечно. Рассчитан на интеграционные тесты.
+pub fn spawn_thermometer_emulator(target_addr: &str, period_ms: u64, format: WireFormat) -> EmulatorHandle {
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_clone = Arc::clone(&stop);
+    let target = target_addr.to_string();
+
+    let join = thread::spawn(move || {
+        let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
+        let mut buf = [0u8; sensor_protocol::FRAME_LEN_WITH_SENSOR];
+        while !stop_clone.load(Ordering::SeqCst) {
+            let temp = rand::random::<f32>() * 30.0;
+            match format {
+                WireFormat::Binary => {
+                    let reading = SensorReading {
+                        timestamp_ms: sensor_protocol::now_ms(),
+                        temperature: temp,
+                        sensor_id: None,
+                    };
+                    let len = sensor_protocol::encode(&mut buf, &reading);
+                    let _ = socket.send_to(&buf[..len], &target);
+                }
+                WireFormat::LegacyAscii => {
+                    let msg = format!("{:.2}", temp);
+                    let _ = socket.send_to(msg.as_bytes(), &target);
+                }
+            }
+            thread::sleep(std::time::Duration::from_millis(period_ms));
+        }
+    });
+
+    EmulatorHandle { stop, join: Some(join) }
+}
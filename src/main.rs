@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use std::thread;
-use smart_house::{room, run_socket_emulator, run_thermometer_emulator, Report, SmartDevice, SmartHouse, SmartSocket, SmartThermometer, TcpSocketDriver, Thermometer, UdpThermometerDriver};
+use smart_house::{room, run_socket_emulator, run_thermometer_emulator, Report, SmartDevice, SmartHouse, SmartSocket, SmartThermometer, TcpSocketDriver, Thermometer, UdpThermometerDriver, WireFormat};
 
 fn main() {
     // // Создаём устройства
@@ -50,18 +50,24 @@ fn main() {
 
     // Включаем эмуляторы в отдельных потоках
     std::thread::spawn(|| run_socket_emulator("127.0.0.1:4000", 1500.0));
-    std::thread::spawn(|| run_thermometer_emulator("127.0.0.1:5000", 1000));
+    std::thread::spawn(|| run_thermometer_emulator("127.0.0.1:5000", 1000, WireFormat::Binary));
 
     std::thread::sleep(std::time::Duration::from_secs(1)); // дать эмуляторам запуститься
 
     let socket_driver = TcpSocketDriver::new("127.0.0.1:4000");
-    let thermo_driver = UdpThermometerDriver::new("127.0.0.1:5000");
+    let thermo_driver = UdpThermometerDriver::new("Термометр1", "127.0.0.1:5000", false);
 
     let mut socket = SmartSocket::new("Розетка1", Box::new(socket_driver));
     let thermometer = SmartThermometer::new("Термометр1", "Гостиная", Box::new(thermo_driver));
 
-    socket.turn_on();
+    socket.turn_on().expect("Ошибка включения розетки");
     thread::sleep(std::time::Duration::from_millis(3000));
-    println!("Мощность: {}", socket.current_power());
-    println!("Температура: {}", thermometer.get_current_temperature());
+    match socket.current_power() {
+        Ok(power) => println!("Мощность: {}", power),
+        Err(e) => println!("Ошибка чтения мощности: {}", e),
+    }
+    match thermometer.get_current_temperature() {
+        Ok(temp) => println!("Температура: {}", temp),
+        Err(e) => println!("Ошибка чтения температуры: {}", e),
+    }
 }
@@ -0,0 +1,129 @@
+//! Компактный бинарный протокол показаний термометра поверх UDP.
+//!
+//! Кадр: 1 байт тега, 8 байт метки времени по часам реального времени (мс
+//! от эпохи Unix), 4 байта температуры (IEEE-754), и необязательный 1 байт
+//! id датчика, если тег это подразумевает. Парсинг — zero-copy из уже
+//! выделенного буфера.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const TAG_READING: u8 = 0x01;
+const TAG_READING_WITH_SENSOR: u8 = 0x02;
+
+/// Минимальная длина кадра без id датчика.
+pub const FRAME_LEN: usize = 13;
+/// Длина кадра с id датчика.
+pub const FRAME_LEN_WITH_SENSOR: usize = FRAME_LEN + 1;
+
+/// Какой формат кадров писать/читать на проводе.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WireFormat {
+    /// Компактный бинарный кадр (см. модуль).
+    Binary,
+    /// Старый формат: ASCII-строка с температурой ("12.30").
+    LegacyAscii,
+}
+
+/// Одно показание термометра, снятое с провода.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SensorReading {
+    pub timestamp_ms: u64,
+    pub temperature: f32,
+    pub sensor_id: Option<u8>,
+}
+
+/// Метка времени по часам реального времени в миллисекундах от эпохи Unix.
+///
+/// На проводе нужна именно часы реального времени, а не монотонные: кадр
+/// читает не тот процесс, что его пишет, а метка от `Instant` осмысленна
+/// только внутри одного процесса. Часы реального времени теоретически
+/// могут прыгнуть назад, но только так метка остаётся сравнимой между
+/// датчиком и потребителем — чем и пользуется `last_reading_timestamp_ms`
+/// для определения протухших показаний.
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Кодирует показание в бинарный кадр и возвращает число записанных байт.
+/// `buf` должен вмещать как минимум `FRAME_LEN_WITH_SENSOR` байт.
+pub fn encode(buf: &mut [u8], reading: &SensorReading) -> usize {
+    buf[0] = if reading.sensor_id.is_some() {
+        TAG_READING_WITH_SENSOR
+    } else {
+        TAG_READING
+    };
+    buf[1..9].copy_from_slice(&reading.timestamp_ms.to_be_bytes());
+    buf[9..13].copy_from_slice(&reading.temperature.to_be_bytes());
+
+    match reading.sensor_id {
+        Some(id) => {
+            buf[13] = id;
+            FRAME_LEN_WITH_SENSOR
+        }
+        None => FRAME_LEN,
+    }
+}
+
+/// Разбирает бинарный кадр из `buf`, без копирования и аллокаций.
+pub fn decode(buf: &[u8]) -> Option<SensorReading> {
+    if buf.len() < FRAME_LEN {
+        return None;
+    }
+    let tag = buf[0];
+    let timestamp_ms = u64::from_be_bytes(buf[1..9].try_into().ok()?);
+    let temperature = f32::from_be_bytes(buf[9..13].try_into().ok()?);
+
+    match tag {
+        TAG_READING => Some(SensorReading {
+            timestamp_ms,
+            temperature,
+            sensor_id: None,
+        }),
+        TAG_READING_WITH_SENSOR if buf.len() >= FRAME_LEN_WITH_SENSOR => Some(SensorReading {
+            timestamp_ms,
+            temperature,
+            sensor_id: Some(buf[13]),
+        }),
+        _ => None,
+    }
+}
+
+/// Разбирает старый ASCII-формат (например `"12.30"`), используемый только
+/// в режиме обратной совместимости.
+pub fn decode_legacy_ascii(buf: &[u8]) -> Option<SensorReading> {
+    let s = std::str::from_utf8(buf).ok()?;
+    let temperature = s.trim().parse::<f32>().ok()?;
+    Some(SensorReading {
+        timestamp_ms: now_ms(),
+        temperature,
+        sensor_id: None,
+    })
+}
+
+/// Небольшой переиспользуемый пул буферов фиксированного размера для
+/// приёма кадров — позволяет нескольким датчикам мультиплексироваться по
+/// одному сокету без аллокации на каждый пакет.
+pub struct BufferPool<const N: usize> {
+    buffers: Vec<[u8; N]>,
+    next: usize,
+}
+
+impl<const N: usize> BufferPool<N> {
+    pub fn new(size: usize) -> Self {
+        Self {
+            buffers: vec![[0u8; N]; size.max(1)],
+            next: 0,
+        }
+    }
+
+    /// Выдаёт следующий буфер пула по кругу, готовый для приёма пакета.
+    pub fn acquire(&mut self) -> &mut [u8; N] {
+        let len = self.buffers.len();
+        let i = self.next;
+        self.next = (i + 1) % len;
+        &mut self.buffers[i]
+    }
+}
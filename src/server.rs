@@ -0,0 +1,281 @@
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::{
+    DeviceError, EmulatorHandle, SmartDevice, SmartHouse, SocketDriver, Thermometer,
+    ThermometerDriver,
+};
+
+/// Запрос к серверу дома, адресующий устройство по паре `(room, device)`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum HouseRequest {
+    ListRooms,
+    ListDevices { room: String },
+    GetReport { room: String, device: String },
+    TurnOn { room: String, device: String },
+    TurnOff { room: String, device: String },
+    ReadState { room: String, device: String },
+    ReadPower { room: String, device: String },
+    ReadTemperature { room: String, device: String },
+}
+
+/// Ответ сервера дома на `HouseRequest`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum HouseResponse {
+    Rooms(Vec<String>),
+    Devices(Vec<String>),
+    Report(String),
+    Ok,
+    State(bool),
+    Power(f32),
+    Temperature(f32),
+    Error(String),
+}
+
+/// Читает один кадр протокола: 4-байтная длина в big-endian, затем полезная
+/// нагрузка. Возвращает `Ok(None)`, если соединение закрылось до начала кадра.
+fn read_frame(stream: &mut TcpStream) -> io::Result<Option<Vec<u8>>> {
+    let mut len_buf = [0u8; 4];
+    match stream.read_exact(&mut len_buf) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let len = u32::from_be_bytes(len_buf) as usize;
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload)?;
+    Ok(Some(payload))
+}
+
+fn write_frame(stream: &mut TcpStream, payload: &[u8]) -> io::Result<()> {
+    let len = (payload.len() as u32).to_be_bytes();
+    stream.write_all(&len)?;
+    stream.write_all(payload)?;
+    stream.flush()
+}
+
+/// Поднимает сервер, обслуживающий весь `SmartHouse` по одному
+/// TCP-соединению на клиента, с кадрированным JSON-протоколом.
+pub fn run_house_server(addr: &str, house: Arc<Mutex<SmartHouse>>) {
+    let listener = TcpListener::bind(addr).unwrap();
+    for stream in listener.incoming() {
+        if let Ok(stream) = stream {
+            let house = Arc::clone(&house);
+            thread::spawn(move || {
+                let _ = handle_house_client(stream, house);
+            });
+        }
+    }
+}
+
+/// Как `run_house_server`, но привязывается к переданному адресу (можно
+/// указать `:0`, чтобы получить свободный порт), возвращает реальный
+/// привязанный адрес и останавливается при `Drop` хендла. Рассчитан на
+/// интеграционные тесты, которым нужно и эфемерный порт, и чистую остановку.
+pub fn spawn_house_server(addr: &str, house: Arc<Mutex<SmartHouse>>) -> (EmulatorHandle, String) {
+    let listener = TcpListener::bind(addr).unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let bound_addr = listener.local_addr().unwrap().to_string();
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_clone = Arc::clone(&stop);
+
+    let join = thread::spawn(move || {
+        for stream in listener.incoming() {
+            if stop_clone.load(Ordering::SeqCst) {
+                break;
+            }
+            match stream {
+                Ok(stream) => {
+                    let house = Arc::clone(&house);
+                    thread::spawn(move || {
+                        let _ = handle_house_client(stream, house);
+                    });
+                }
+                Err(_) => thread::sleep(std::time::Duration::from_millis(20)),
+            }
+        }
+    });
+
+    (EmulatorHandle::new(stop, join), bound_addr)
+}
+
+fn handle_house_client(mut stream: TcpStream, house: Arc<Mutex<SmartHouse>>) -> io::Result<()> {
+    loop {
+        let payload = match read_frame(&mut stream)? {
+            Some(p) => p,
+            None => return Ok(()),
+        };
+
+        let response = match serde_json::from_slice::<HouseRequest>(&payload) {
+            Ok(request) => process_request(&house, request),
+            Err(e) => HouseResponse::Error(format!("не удалось разобрать запрос: {}", e)),
+        };
+
+        let bytes =
+            serde_json::to_vec(&response).expect("HouseResponse должен сериализоваться");
+        write_frame(&mut stream, &bytes)?;
+    }
+}
+
+fn process_request(house: &Arc<Mutex<SmartHouse>>, request: HouseRequest) -> HouseResponse {
+    let mut house = house.lock().unwrap();
+    match request {
+        HouseRequest::ListRooms => HouseResponse::Rooms(house.room_names()),
+        HouseRequest::ListDevices { room } => match house.get_room(&room) {
+            Some(r) => HouseResponse::Devices(r.device_names()),
+            None => HouseResponse::Error(crate::SmartHouseError::RoomNotFound(room).to_string()),
+        },
+        HouseRequest::GetReport { room, device } => {
+            match house.get_device(&room, &device) {
+                Ok(dev) => HouseResponse::Report(format_device(dev)),
+                Err(e) => HouseResponse::Error(e.to_string()),
+            }
+        }
+        HouseRequest::TurnOn { room, device } => with_socket(&mut house, &room, &device, |s| {
+            s.turn_on().map(|()| HouseResponse::Ok)
+        }),
+        HouseRequest::TurnOff { room, device } => with_socket(&mut house, &room, &device, |s| {
+            s.turn_off().map(|()| HouseResponse::Ok)
+        }),
+        HouseRequest::ReadState { room, device } => with_socket(&mut house, &room, &device, |s| {
+            s.is_on().map(HouseResponse::State)
+        }),
+        HouseRequest::ReadPower { room, device } => with_socket(&mut house, &room, &device, |s| {
+            s.current_power().map(HouseResponse::Power)
+        }),
+        HouseRequest::ReadTemperature { room, device } => {
+            with_thermometer(&mut house, &room, &device, |t| {
+                t.get_current_temperature().map(HouseResponse::Temperature)
+            })
+        }
+    }
+}
+
+fn format_device(device: &SmartDevice) -> String {
+    match device {
+        SmartDevice::Socket(s) => format!("{}", s),
+        SmartDevice::Thermometer(t) => format!("{}", t),
+    }
+}
+
+fn with_socket(
+    house: &mut SmartHouse,
+    room: &str,
+    device: &str,
+    f: impl FnOnce(&mut crate::SmartSocket) -> Result<HouseResponse, DeviceError>,
+) -> HouseResponse {
+    match house.get_device_mut(room, device) {
+        Ok(SmartDevice::Socket(s)) => f(s).unwrap_or_else(|e| HouseResponse::Error(e.to_string())),
+        Ok(_) => HouseResponse::Error(format!("устройство '{}' не является розеткой", device)),
+        Err(e) => HouseResponse::Error(e.to_string()),
+    }
+}
+
+fn with_thermometer(
+    house: &mut SmartHouse,
+    room: &str,
+    device: &str,
+    f: impl FnOnce(&crate::SmartThermometer) -> Result<HouseResponse, DeviceError>,
+) -> HouseResponse {
+    match house.get_device_mut(room, device) {
+        Ok(SmartDevice::Thermometer(t)) => {
+            f(t).unwrap_or_else(|e| HouseResponse::Error(e.to_string()))
+        }
+        Ok(_) => HouseResponse::Error(format!("устройство '{}' не является термометром", device)),
+        Err(e) => HouseResponse::Error(e.to_string()),
+    }
+}
+
+/// Драйвер, адресующий устройство `(room, device)` удалённого `SmartHouse`
+/// через сервер `run_house_server`, так что клиент может управлять домом так,
+/// будто тот локальный.
+#[derive(Clone, Debug)]
+pub struct HouseClientDriver {
+    server_addr: String,
+    room: String,
+    device: String,
+}
+
+impl HouseClientDriver {
+    pub fn new(server_addr: &str, room: &str, device: &str) -> Self {
+        Self {
+            server_addr: server_addr.to_string(),
+            room: room.to_string(),
+            device: device.to_string(),
+        }
+    }
+
+    fn call(&self, request: HouseRequest) -> Result<HouseResponse, DeviceError> {
+        let mut stream = TcpStream::connect(&self.server_addr)?;
+        let payload = serde_json::to_vec(&request)
+            .map_err(|e| DeviceError::Protocol(e.to_string()))?;
+        write_frame(&mut stream, &payload)?;
+
+        let response_bytes = read_frame(&mut stream)?.ok_or_else(|| {
+            DeviceError::Protocol("сервер закрыл соединение без ответа".to_string())
+        })?;
+        serde_json::from_slice(&response_bytes).map_err(|e| DeviceError::Protocol(e.to_string()))
+    }
+
+    fn addressed(&self) -> (String, String) {
+        (self.room.clone(), self.device.clone())
+    }
+}
+
+impl SocketDriver for HouseClientDriver {
+    type Error = DeviceError;
+
+    fn turn_on(&mut self) -> Result<(), DeviceError> {
+        let (room, device) = self.addressed();
+        match self.call(HouseRequest::TurnOn { room, device })? {
+            HouseResponse::Ok => Ok(()),
+            HouseResponse::Error(e) => Err(DeviceError::Protocol(e)),
+            _ => Err(DeviceError::Protocol("неожиданный ответ сервера".to_string())),
+        }
+    }
+
+    fn turn_off(&mut self) -> Result<(), DeviceError> {
+        let (room, device) = self.addressed();
+        match self.call(HouseRequest::TurnOff { room, device })? {
+            HouseResponse::Ok => Ok(()),
+            HouseResponse::Error(e) => Err(DeviceError::Protocol(e)),
+            _ => Err(DeviceError::Protocol("неожиданный ответ сервера".to_string())),
+        }
+    }
+
+    fn is_on(&self) -> Result<bool, DeviceError> {
+        let (room, device) = self.addressed();
+        match self.call(HouseRequest::ReadState { room, device })? {
+            HouseResponse::State(on) => Ok(on),
+            HouseResponse::Error(e) => Err(DeviceError::Protocol(e)),
+            _ => Err(DeviceError::Protocol("неожиданный ответ сервера".to_string())),
+        }
+    }
+
+    fn current_power(&self) -> Result<f32, DeviceError> {
+        let (room, device) = self.addressed();
+        match self.call(HouseRequest::ReadPower { room, device })? {
+            HouseResponse::Power(power) => Ok(power),
+            HouseResponse::Error(e) => Err(DeviceError::Protocol(e)),
+            _ => Err(DeviceError::Protocol("неожиданный ответ сервера".to_string())),
+        }
+    }
+}
+
+impl ThermometerDriver for HouseClientDriver {
+    type Error = DeviceError;
+
+    fn latest_temperature(&self) -> Result<f32, DeviceError> {
+        let (room, device) = self.addressed();
+        match self.call(HouseRequest::ReadTemperature { room, device })? {
+            HouseResponse::Temperature(temp) => Ok(temp),
+            HouseResponse::Error(e) => Err(DeviceError::Protocol(e)),
+            _ => Err(DeviceError::Protocol("неожиданный ответ сервера".to_string())),
+        }
+    }
+}
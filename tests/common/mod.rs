@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::net::TcpStream;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use smart_house::{
+    room, spawn_socket_emulator, spawn_thermometer_emulator, EmulatorHandle, SmartDevice,
+    SmartHouse, SmartSocket, SmartThermometer, TcpSocketDriver, Thermometer, UdpThermometerDriver,
+    WireFormat,
+};
+
+const ROOM: &str = "Гостиная";
+const SOCKET: &str = "Розетка1";
+const THERMOMETER: &str = "Термометр1";
+
+/// Последнее увиденное состояние одного устройства, собранное `TestHarness`.
+#[derive(Debug, Clone, Default)]
+pub struct ObservedState {
+    pub is_on: Option<bool>,
+    pub power: Option<f32>,
+    pub temperature: Option<f32>,
+}
+
+/// Владеет жизненным циклом эмуляторов TCP-розетки и UDP-термометра для
+/// интеграционных тестов: поднимает их на свободных портах, дожидается
+/// готовности вместо фиксированного `sleep`, собирает `SmartHouse` поверх
+/// них и хранит зеркало последнего наблюдаемого состояния устройств.
+pub struct TestHarness {
+    pub socket_addr: String,
+    pub thermometer_addr: String,
+    house: SmartHouse,
+    observed: Mutex<HashMap<(String, String), ObservedState>>,
+    _socket_emulator: EmulatorHandle,
+    _thermometer_emulator: EmulatorHandle,
+}
+
+impl TestHarness {
+    pub fn new() -> Self {
+        let (socket_emulator, socket_addr) = spawn_socket_emulator("127.0.0.1:0", 1500.0);
+        wait_until_accepting(&socket_addr, Duration::from_secs(2));
+
+        let thermo_driver = UdpThermometerDriver::new(THERMOMETER, "127.0.0.1:0", false);
+        let thermometer_addr = thermo_driver.local_addr().to_string();
+        let thermometer_emulator =
+            spawn_thermometer_emulator(&thermometer_addr, 50, WireFormat::Binary);
+        wait_until_reading(&thermo_driver, Duration::from_secs(2));
+
+        let socket = SmartSocket::new(SOCKET, Box::new(TcpSocketDriver::new(&socket_addr)));
+        let thermometer = SmartThermometer::new(THERMOMETER, ROOM, Box::new(thermo_driver));
+
+        let d1 = SmartDevice::Socket(socket);
+        let d2 = SmartDevice::Thermometer(thermometer);
+        let room = room!(ROOM, (d1.name(), d1), (d2.name(), d2));
+        let house = SmartHouse::new(HashMap::from([(room.name().clone(), room)]));
+
+        Self {
+            socket_addr,
+            thermometer_addr,
+            house,
+            observed: Mutex::new(HashMap::new()),
+            _socket_emulator: socket_emulator,
+            _thermometer_emulator: thermometer_emulator,
+        }
+    }
+
+    pub fn house(&self) -> &SmartHouse {
+        &self.house
+    }
+
+    pub fn house_mut(&mut self) -> &mut SmartHouse {
+        &mut self.house
+    }
+
+    fn refresh(&self, device: &str) {
+        let Ok(dev) = self.house.get_device(ROOM, device) else {
+            return;
+        };
+        let mut observed = self.observed.lock().unwrap();
+        let entry = observed
+            .entry((ROOM.to_string(), device.to_string()))
+            .or_default();
+        match dev {
+            SmartDevice::Socket(s) => {
+                entry.is_on = s.is_on().ok();
+                entry.power = s.current_power().ok();
+            }
+            SmartDevice::Thermometer(t) => {
+                entry.temperature = t.get_current_temperature().ok();
+            }
+        }
+    }
+
+    /// Опрашивает `device`, пока наблюдаемое состояние не удовлетворит
+    /// `predicate`, или пока не истечёт `timeout`. Возвращает `true`, если
+    /// условие было выполнено.
+    pub fn expect_until(
+        &self,
+        device: &str,
+        predicate: impl Fn(&ObservedState) -> bool,
+        timeout: Duration,
+    ) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            self.refresh(device);
+            {
+                let observed = self.observed.lock().unwrap();
+                if let Some(state) = observed.get(&(ROOM.to_string(), device.to_string())) {
+                    if predicate(state) {
+                        return true;
+                    }
+                }
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+}
+
+fn wait_until_accepting(addr: &str, timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if TcpStream::connect(addr).is_ok() {
+            return;
+        }
+        if Instant::now() >= deadline {
+            panic!("эмулятор розетки на '{}' не принял соединение вовремя", addr);
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn wait_until_reading(driver: &UdpThermometerDriver, timeout: Duration) {
+    use smart_house::ThermometerDriver;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if driver.latest_temperature().is_ok() {
+            return;
+        }
+        if Instant::now() >= deadline {
+            panic!("термометр не прислал ни одного показания вовремя");
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
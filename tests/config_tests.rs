@@ -0,0 +1,69 @@
+use std::fs;
+
+use smart_house::{SmartDevice, SmartHouse, Thermometer};
+
+#[test]
+fn test_from_config_round_trip() {
+    let toml = r#"
+        [[room]]
+        name = "Кухня"
+
+        [[room.device]]
+        kind = "socket"
+        name = "Чайник"
+        driver = { type = "mock", state = true, power = 1500.0 }
+
+        [[room.device]]
+        kind = "thermometer"
+        name = "Датчик1"
+        driver = { type = "mock", temperature = 21.5 }
+    "#;
+
+    let path = std::env::temp_dir().join(format!("smart_house_test_{}.toml", std::process::id()));
+    fs::write(&path, toml).expect("не удалось записать временный файл конфигурации");
+
+    let house = SmartHouse::from_config(&path).expect("конфигурация должна разобраться");
+    fs::remove_file(&path).ok();
+
+    assert_eq!(house.room_names(), vec!["Кухня".to_string()]);
+
+    match house.get_device("Кухня", "Чайник") {
+        Ok(SmartDevice::Socket(socket)) => {
+            assert_eq!(socket.is_on().unwrap(), true);
+            assert_eq!(socket.current_power().unwrap(), 1500.0);
+        }
+        other => panic!("ожидалась розетка, получено {:?}", other),
+    }
+
+    match house.get_device("Кухня", "Датчик1") {
+        Ok(SmartDevice::Thermometer(thermometer)) => {
+            assert_eq!(thermometer.get_current_temperature().unwrap(), 21.5);
+        }
+        other => panic!("ожидался термометр, получено {:?}", other),
+    }
+}
+
+#[test]
+fn test_from_config_rejects_unknown_kind() {
+    let toml = r#"
+        [[room]]
+        name = "Кухня"
+
+        [[room.device]]
+        kind = "лампочка"
+        name = "Лампа1"
+        driver = { type = "mock" }
+    "#;
+
+    let path =
+        std::env::temp_dir().join(format!("smart_house_test_bad_{}.toml", std::process::id()));
+    fs::write(&path, toml).expect("не удалось записать временный файл конфигурации");
+
+    let result = SmartHouse::from_config(&path);
+    fs::remove_file(&path).ok();
+
+    match result {
+        Err(smart_house::ConfigError::UnknownKind { kind }) => assert_eq!(kind, "лампочка"),
+        other => panic!("ожидалась ошибка UnknownKind, получено {:?}", other),
+    }
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use std::time::Duration;
+
+use common::TestHarness;
+use smart_house::SmartDevice;
+
+#[test]
+fn test_socket_reports_power_after_turn_on() {
+    let mut harness = TestHarness::new();
+
+    match harness.house_mut().get_device_mut("Гостиная", "Розетка1") {
+        Ok(SmartDevice::Socket(socket)) => {
+            socket.turn_on().expect("не удалось включить розетку");
+        }
+        other => panic!("ожидалась розетка, получено {:?}", other),
+    }
+
+    let reported = harness.expect_until(
+        "Розетка1",
+        |state| matches!(state.power, Some(power) if power > 0.0),
+        Duration::from_secs(2),
+    );
+
+    assert!(reported, "розетка не отдала мощность > 0 Вт вовремя");
+}
+
+#[test]
+fn test_thermometer_reports_temperature() {
+    let harness = TestHarness::new();
+
+    let reported = harness.expect_until(
+        "Термометр1",
+        |state| state.temperature.is_some(),
+        Duration::from_secs(2),
+    );
+
+    assert!(reported, "термометр не прислал показание вовремя");
+}
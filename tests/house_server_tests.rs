@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use smart_house::{
+    room, spawn_house_server, HouseClientDriver, MockSocketDriver, SmartDevice, SmartHouse,
+    SmartSocket, SocketDriver,
+};
+
+#[test]
+fn test_house_client_driver_turn_on_and_read_power_round_trip() {
+    let socket = SmartDevice::Socket(SmartSocket::new(
+        "Розетка1",
+        Box::new(MockSocketDriver::new(false, 750.0)),
+    ));
+    let room = room!("Гостиная", ("Розетка1".to_string(), socket));
+    let house = Arc::new(Mutex::new(SmartHouse::new(HashMap::from([(
+        room.name().clone(),
+        room,
+    )]))));
+
+    let (_server, server_addr) = spawn_house_server("127.0.0.1:0", Arc::clone(&house));
+
+    let mut driver = HouseClientDriver::new(&server_addr, "Гостиная", "Розетка1");
+    assert_eq!(driver.is_on().expect("is_on"), false);
+
+    driver.turn_on().expect("turn_on");
+    assert_eq!(driver.is_on().expect("is_on после turn_on"), true);
+    assert_eq!(driver.current_power().expect("current_power"), 750.0);
+}
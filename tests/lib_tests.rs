@@ -2,12 +2,17 @@ use std::collections::HashMap;
 
 use smart_house::{
     room,
-    Report, Room, SmartDevice, SmartHouse, SmartHouseError, SmartSocket, SmartThermometer,
+    MockSocketDriver, MockThermometerDriver, Report, Room, SmartDevice, SmartHouse,
+    SmartHouseError, SmartSocket, SmartThermometer,
 };
 
 #[test]
 fn test_add_and_get_device_in_room() {
-    let thermometer = SmartDevice::Thermometer(SmartThermometer::new("T1", "Кухня"));
+    let thermometer = SmartDevice::Thermometer(SmartThermometer::new(
+        "T1",
+        "Кухня",
+        Box::new(MockThermometerDriver::new(20.0)),
+    ));
     let mut room = Room::new("Кухня", HashMap::new());
 
     room.add_device(thermometer);
@@ -16,7 +21,10 @@ fn test_add_and_get_device_in_room() {
 
 #[test]
 fn test_remove_device_from_room() {
-    let socket = SmartDevice::Socket(SmartSocket::new("S1", true, 10.0));
+    let socket = SmartDevice::Socket(SmartSocket::new(
+        "S1",
+        Box::new(MockSocketDriver::new(true, 10.0)),
+    ));
     let mut room = Room::new("Гостиная", HashMap::new());
     room.add_device(socket);
     let removed = room.remove_device("S1");
@@ -40,7 +48,10 @@ fn test_add_and_remove_room_in_house() {
 
 #[test]
 fn test_get_device_from_house() {
-    let socket = SmartDevice::Socket(SmartSocket::new("S1", true, 15.0));
+    let socket = SmartDevice::Socket(SmartSocket::new(
+        "S1",
+        Box::new(MockSocketDriver::new(true, 15.0)),
+    ));
     let room = room!("Кабинет", ("S1".to_string(), socket));
     let mut house = SmartHouse::new(HashMap::new());
     house.add_room(room);
@@ -62,7 +73,10 @@ fn test_get_device_error_handling() {
 
 #[test]
 fn test_report_trait() {
-    let socket = SmartDevice::Socket(SmartSocket::new("S1", true, 20.0));
+    let socket = SmartDevice::Socket(SmartSocket::new(
+        "S1",
+        Box::new(MockSocketDriver::new(true, 20.0)),
+    ));
     let room = room!("Ванная", ("S1".to_string(), socket));
     let house = SmartHouse::new(HashMap::from([("Ванная".to_string(), room)]));
 
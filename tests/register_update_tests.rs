@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use smart_house::{
+    room, DeviceUpdate, MockSocketDriver, SmartDevice, SmartHouse, SmartSocket,
+};
+
+#[test]
+fn test_register_update_fires_on_state_change() {
+    let socket = SmartDevice::Socket(SmartSocket::new(
+        "Розетка1",
+        Box::new(MockSocketDriver::new(false, 0.0)),
+    ));
+    let room = room!("Гостиная", ("Розетка1".to_string(), socket));
+    let mut house = SmartHouse::new(HashMap::from([(room.name().clone(), room)]));
+
+    let updates: Arc<Mutex<Vec<DeviceUpdate>>> = Arc::new(Mutex::new(Vec::new()));
+    let updates_clone = Arc::clone(&updates);
+    house.register_update(move |update| {
+        updates_clone.lock().unwrap().push(update.clone());
+    });
+
+    match house.get_device_mut("Гостиная", "Розетка1") {
+        Ok(SmartDevice::Socket(socket)) => {
+            socket.turn_on().expect("не удалось включить розетку");
+        }
+        other => panic!("ожидалась розетка, получено {:?}", other),
+    }
+
+    let seen = updates.lock().unwrap();
+    assert!(
+        seen.iter().any(|update| matches!(
+            update,
+            DeviceUpdate::SocketState { device, is_on: true, .. } if device == "Розетка1"
+        )),
+        "register_update не доставил уведомление о включении розетки: {:?}",
+        *seen
+    );
+}